@@ -0,0 +1,315 @@
+//
+//  SOS: the Stupid Operating System
+//  by Eliza Weisman (eliza@elizas.website)
+//
+//  Copyright (c) 2015-2017 Eliza Weisman
+//  Released under the terms of the MIT license. See `LICENSE` in the root
+//  directory of this repository for more information.
+//! Decompression of `SHF_COMPRESSED` sections.
+//!
+//! Modern toolchains may emit debug sections (`.debug_*`) compressed to
+//! save space. A compressed section's data begins with a
+//! [`CompressionHeader`](struct.CompressionHeader.html) describing the
+//! compression algorithm and the uncompressed size, followed by the
+//! compressed stream itself.
+
+/// `ELFCOMPRESS_ZLIB`: the section is compressed with zlib.
+pub const ELFCOMPRESS_ZLIB: u32 = 1;
+/// `ELFCOMPRESS_ZSTD`: the section is compressed with Zstandard.
+pub const ELFCOMPRESS_ZSTD: u32 = 2;
+
+/// The header prepended to the data of an `SHF_COMPRESSED` section.
+///
+/// Refer to the [ELF standard](http://www.sco.com/developers/gabi/latest/ch4.sheader.html#compression_header)
+/// for more information.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CompressionHeader {
+    /// The compression algorithm used (`ELFCOMPRESS_ZLIB` or
+    /// `ELFCOMPRESS_ZSTD`).
+    pub ch_type: u32
+  , /// Reserved; must be 0.
+    pub ch_reserved: u32
+  , /// The size of the section's uncompressed data.
+    pub ch_size: u64
+  , /// The required alignment of the uncompressed data.
+    pub ch_addralign: u64
+}
+
+/// Errors that can occur while decompressing a section.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The section is not `SHF_COMPRESSED`, its data is shorter than its
+    /// `CompressionHeader`, or the compressed stream ends before producing
+    /// `ch_size` bytes of output.
+    Truncated
+  , /// `ch_type` names an algorithm we don't know how to decompress.
+    UnknownType(u32)
+  , /// The caller-provided output buffer is smaller than `ch_size`, or the
+    /// compressed stream produces more bytes than the output slice holds.
+    OutputTooSmall
+  , /// The compressed stream is malformed.
+    Corrupt
+}
+
+/// Decompresses a zlib-wrapped DEFLATE stream (RFC 1950 / RFC 1951) into
+/// `out`, which must be exactly as large as the uncompressed data.
+pub fn inflate_zlib(input: &[u8], out: &mut [u8]) -> Result<(), Error> {
+    // The zlib wrapper is a 2-byte header (CMF, FLG) and a trailing 4-byte
+    // Adler-32 checksum, which we do not bother verifying.
+    if input.len() < 6 {
+        return Err(Error::Truncated);
+    }
+    inflate_raw(&input[2..], out)
+}
+
+/// Decompresses a raw DEFLATE stream (RFC 1951) into `out`, which must be
+/// exactly as large as the uncompressed data.
+///
+/// Back-references are satisfied out of `out` itself, since DEFLATE's
+/// sliding window never needs to look further back than the data already
+/// produced; this lets decompression run without a heap.
+pub fn inflate_raw(input: &[u8], out: &mut [u8]) -> Result<(), Error> {
+    let mut bits = BitReader::new(input);
+    let mut written = 0usize;
+
+    loop {
+        let is_final = bits.read_bits(1)? == 1;
+        let block_type = bits.read_bits(2)?;
+
+        match block_type {
+            0 => written = inflate_stored(&mut bits, out, written)?
+          , 1 => written = inflate_huffman(&mut bits, out, written, &fixed_lit_tree(), &fixed_dist_tree())?
+          , 2 => {
+                let (lit_tree, dist_tree) = read_dynamic_trees(&mut bits)?;
+                written = inflate_huffman(&mut bits, out, written, &lit_tree, &dist_tree)?;
+            }
+          , _ => return Err(Error::Corrupt)
+        }
+
+        if is_final {
+            break;
+        }
+    }
+
+    if written == out.len() { Ok(()) } else { Err(Error::Truncated) }
+}
+
+/// Reads bits least-significant-bit first out of a byte slice, as DEFLATE
+/// requires.
+struct BitReader<'a> {
+    data: &'a [u8]
+  , byte: usize
+  , bit: u32
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        BitReader { data: data, byte: 0, bit: 0 }
+    }
+
+    fn read_bits(&mut self, count: u32) -> Result<u32, Error> {
+        let mut value = 0u32;
+        for i in 0..count {
+            if self.byte >= self.data.len() {
+                return Err(Error::Truncated);
+            }
+            let bit = (self.data[self.byte] >> self.bit) & 1;
+            value |= (bit as u32) << i;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.byte += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    /// Discards any partial byte, leaving the reader aligned on a byte
+    /// boundary.
+    fn align(&mut self) {
+        if self.bit != 0 {
+            self.bit = 0;
+            self.byte += 1;
+        }
+    }
+}
+
+/// A canonical Huffman decode table: for each code length, the symbols
+/// sharing that length, sorted by their canonical code value.
+///
+/// Decoding walks bit-by-bit rather than through a lookup table; section
+/// decompression is not a hot path, so we favor simplicity.
+struct HuffmanTree {
+    /// `counts[len]` = the number of codes of length `len`.
+    counts: [u16; 16]
+  , /// Symbols, ordered first by code length, then by code value.
+    symbols: [u16; 288]
+  , len: usize
+}
+
+impl HuffmanTree {
+    fn from_lengths(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; 16];
+        for len in 1..16 {
+            offsets[len] = offsets[len - 1] + counts[len - 1];
+        }
+
+        let mut symbols = [0u16; 288];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+
+        HuffmanTree { counts: counts, symbols: symbols, len: lengths.len() }
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> Result<u16, Error> {
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+        for len in 1..16 {
+            code |= bits.read_bits(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + (code - first)) as usize]);
+            }
+            index += count;
+            first += count;
+            first <<= 1;
+            code <<= 1;
+        }
+        let _ = self.len;
+        Err(Error::Corrupt)
+    }
+}
+
+fn fixed_lit_tree() -> HuffmanTree {
+    let mut lengths = [0u8; 288];
+    for i in 0..144 { lengths[i] = 8; }
+    for i in 144..256 { lengths[i] = 9; }
+    for i in 256..280 { lengths[i] = 7; }
+    for i in 280..288 { lengths[i] = 8; }
+    HuffmanTree::from_lengths(&lengths)
+}
+
+fn fixed_dist_tree() -> HuffmanTree {
+    let lengths = [5u8; 30];
+    HuffmanTree::from_lengths(&lengths)
+}
+
+const CODE_LENGTH_ORDER: [usize; 19] =
+    [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+const LENGTH_BASE: [u16; 29] =
+    [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59,
+     67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u32; 29] =
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5,
+     5, 5, 5, 0];
+const DIST_BASE: [u32; 30] =
+    [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513,
+     769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DIST_EXTRA: [u32; 30] =
+    [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10,
+     11, 11, 12, 12, 13, 13];
+
+fn read_dynamic_trees(bits: &mut BitReader) -> Result<(HuffmanTree, HuffmanTree), Error> {
+    let hlit = bits.read_bits(5)? as usize + 257;
+    let hdist = bits.read_bits(5)? as usize + 1;
+    let hclen = bits.read_bits(4)? as usize + 4;
+
+    let mut cl_lengths = [0u8; 19];
+    for i in 0..hclen {
+        cl_lengths[CODE_LENGTH_ORDER[i]] = bits.read_bits(3)? as u8;
+    }
+    let cl_tree = HuffmanTree::from_lengths(&cl_lengths);
+
+    let mut lengths = [0u8; 288 + 32];
+    let total = hlit + hdist;
+    let mut i = 0;
+    while i < total {
+        let symbol = cl_tree.decode(bits)?;
+        match symbol {
+            0...15 => {
+                lengths[i] = symbol as u8;
+                i += 1;
+            }
+          , 16 => {
+                if i == 0 { return Err(Error::Corrupt); }
+                let prev = lengths[i - 1];
+                let repeat = bits.read_bits(2)? as usize + 3;
+                for _ in 0..repeat {
+                    if i >= total { return Err(Error::Corrupt); }
+                    lengths[i] = prev;
+                    i += 1;
+                }
+            }
+          , 17 => {
+                let repeat = bits.read_bits(3)? as usize + 3;
+                i += repeat;
+            }
+          , 18 => {
+                let repeat = bits.read_bits(7)? as usize + 11;
+                i += repeat;
+            }
+          , _ => return Err(Error::Corrupt)
+        }
+    }
+
+    let lit_tree = HuffmanTree::from_lengths(&lengths[..hlit]);
+    let dist_tree = HuffmanTree::from_lengths(&lengths[hlit..hlit + hdist]);
+    Ok((lit_tree, dist_tree))
+}
+
+fn inflate_stored(bits: &mut BitReader, out: &mut [u8], written: usize) -> Result<usize, Error> {
+    bits.align();
+    let len = bits.read_bits(16)? as usize;
+    let _nlen = bits.read_bits(16)?;
+    let mut written = written;
+    for _ in 0..len {
+        if written >= out.len() { return Err(Error::OutputTooSmall); }
+        out[written] = bits.read_bits(8)? as u8;
+        written += 1;
+    }
+    Ok(written)
+}
+
+fn inflate_huffman(bits: &mut BitReader, out: &mut [u8], written: usize, lit_tree: &HuffmanTree, dist_tree: &HuffmanTree) -> Result<usize, Error> {
+    let mut written = written;
+    loop {
+        let symbol = lit_tree.decode(bits)?;
+        if symbol < 256 {
+            if written >= out.len() { return Err(Error::OutputTooSmall); }
+            out[written] = symbol as u8;
+            written += 1;
+        } else if symbol == 256 {
+            return Ok(written);
+        } else {
+            let idx = (symbol - 257) as usize;
+            if idx >= LENGTH_BASE.len() { return Err(Error::Corrupt); }
+            let length = LENGTH_BASE[idx] as usize + bits.read_bits(LENGTH_EXTRA[idx])? as usize;
+
+            let dist_symbol = dist_tree.decode(bits)? as usize;
+            if dist_symbol >= DIST_BASE.len() { return Err(Error::Corrupt); }
+            let distance = DIST_BASE[dist_symbol] as usize + bits.read_bits(DIST_EXTRA[dist_symbol])? as usize;
+
+            if distance > written { return Err(Error::Corrupt); }
+            let mut src = written - distance;
+            for _ in 0..length {
+                if written >= out.len() { return Err(Error::OutputTooSmall); }
+                out[written] = out[src];
+                written += 1;
+                src += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//
+//  SOS: the Stupid Operating System
+//  by Eliza Weisman (eliza@elizas.website)
+//
+//  Copyright (c) 2015-2017 Eliza Weisman
+//  Released under the terms of the MIT license. See `LICENSE` in the root
+//  directory of this repository for more information.
+//! COMDAT section-group deduplication.
+//!
+//! Objects built with `-ffunction-sections` or from template instantiations
+//! often contain several `SHT_GROUP` sections marked `GRP_COMDAT` that share
+//! the same signature symbol -- one per translation unit that instantiated
+//! the same template or emitted the same inline function. A static linker
+//! keeps only the first such group and discards the rest; `dedup_comdat_groups`
+//! does the same for a loader, so that loading an object that was never
+//! linked doesn't produce duplicate-symbol errors.
+
+use elf::section::{Contents, GroupFlags, Header, Type, GRP_COMDAT};
+use elf::strtab::StrTab;
+
+/// Marks the duplicate members of every `GRP_COMDAT` group in `sections`
+/// as excluded, so a loader can skip them.
+///
+/// `excluded` must have one entry per section in `sections`; entries are
+/// only ever set to `true`; callers that want to track exclusions for
+/// other reasons should leave theirs set going in.
+pub fn dedup_comdat_groups(sections: &[Header], data: &[u8], excluded: &mut [bool]) {
+    assert_eq!(sections.len(), excluded.len());
+
+    for i in 0..sections.len() {
+        if excluded[i] || sections[i].section_type() != Type::Group {
+            continue;
+        }
+
+        let (flags, members) = match sections[i].contents(data) {
+            Contents::Group { flags, indicies } => (flags, indicies)
+          , _ => continue
+        };
+        if !GroupFlags::from_bits_truncate(*flags).contains(GRP_COMDAT) {
+            continue;
+        }
+
+        let signature = match group_signature(&sections[i], sections, data) {
+            Some(name) => name
+          , None => continue
+        };
+
+        let is_duplicate = (0..i).any(|j| {
+            !excluded[j]
+                && sections[j].section_type() == Type::Group
+                && group_signature(&sections[j], sections, data) == Some(signature)
+        });
+
+        if is_duplicate {
+            excluded[i] = true;
+            for &member in members {
+                if let Some(flag) = excluded.get_mut(member as usize) {
+                    *flag = true;
+                }
+            }
+        }
+    }
+}
+
+/// Resolves a group section's signature symbol: the symbol named by its
+/// `info` field, in the symbol table named by its `link` field.
+fn group_signature<'a>(group: &Header, sections: &'a [Header], data: &'a [u8]) -> Option<&'a str> {
+    let symtab = sections.get(group.link() as usize)?;
+    let strtab_section = sections.get(symtab.link() as usize)?;
+    let strtab = StrTab::new(strtab_section.raw_data(data));
+    let sym = symtab.symbols(data).nth(group.info() as usize)?;
+    Some(sym.name(&strtab))
+}
@@ -0,0 +1,184 @@
+//
+//  SOS: the Stupid Operating System
+//  by Eliza Weisman (eliza@elizas.website)
+//
+//  Copyright (c) 2015-2017 Eliza Weisman
+//  Released under the terms of the MIT license. See `LICENSE` in the root
+//  directory of this repository for more information.
+//! Symbol hash tables (`SHT_HASH` and `SHT_GNU_HASH`).
+//!
+//! These give O(1) lookup of a dynamic symbol by name, rather than a
+//! linear scan of the symbol table.
+
+use elf::strtab::StrTab;
+use elf::symbol::Symbol;
+
+use core::slice;
+
+/// Computes the classic SysV ELF hash (`elf_hash`) of a symbol name.
+pub fn elf_hash(name: &[u8]) -> u32 {
+    let mut h: u32 = 0;
+    for &c in name {
+        h = h.wrapping_shl(4).wrapping_add(c as u32);
+        let g = h & 0xf0000000;
+        if g != 0 {
+            h ^= g >> 24;
+        }
+        h &= !g;
+    }
+    h
+}
+
+/// Computes the GNU hash (`DT_GNU_HASH`) of a symbol name.
+pub fn gnu_hash(name: &[u8]) -> u32 {
+    let mut h: u32 = 5381;
+    for &c in name {
+        h = h.wrapping_mul(33).wrapping_add(c as u32);
+    }
+    h
+}
+
+/// A classic SysV symbol hash table (`SHT_HASH`).
+pub struct HashTable<'a> {
+    bucket: &'a [u32]
+  , chain: &'a [u32]
+}
+
+impl<'a> HashTable<'a> {
+    /// Parses a `HashTable` out of the raw bytes of an `SHT_HASH` section.
+    ///
+    /// Returns `None` if `data` is too short to hold a well-formed table.
+    pub fn new(data: &'a [u8]) -> Option<Self> {
+        if data.len() < 8 {
+            return None;
+        }
+        let words = data.as_ptr() as *const u32;
+        let nbucket = unsafe { *words } as usize;
+        let nchain = unsafe { *words.offset(1) } as usize;
+        if data.len() < 8 + (nbucket + nchain) * 4 {
+            return None;
+        }
+        let bucket = unsafe { slice::from_raw_parts(words.offset(2), nbucket) };
+        let chain = unsafe { slice::from_raw_parts(words.offset(2 + nbucket as isize), nchain) };
+        Some(HashTable { bucket: bucket, chain: chain })
+    }
+
+    /// Looks up `name` in `symtab`, whose entries' names are resolved
+    /// through `strtab`.
+    pub fn lookup(&self, name: &str, symtab: &'a [Symbol], strtab: &StrTab<'a>) -> Option<&'a Symbol> {
+        if self.bucket.is_empty() {
+            return None;
+        }
+        let hash = elf_hash(name.as_bytes());
+        let mut y = self.bucket[hash as usize % self.bucket.len()] as usize;
+        while y != 0 {
+            let sym = match symtab.get(y) {
+                Some(sym) => sym
+              , None => return None
+            };
+            if sym.name(strtab) == name {
+                return Some(sym);
+            }
+            y = match self.chain.get(y) {
+                Some(&next) => next as usize
+              , None => return None
+            };
+        }
+        None
+    }
+}
+
+/// A GNU-style symbol hash table (`SHT_GNU_HASH`), which adds a Bloom
+/// filter to quickly rule out symbols that are definitely absent.
+pub struct GnuHashTable<'a> {
+    symoffset: u32
+  , bloom_shift: u32
+  , bloom: &'a [u64]
+  , bucket: &'a [u32]
+  , chain: &'a [u32]
+}
+
+impl<'a> GnuHashTable<'a> {
+    /// Parses a `GnuHashTable` out of the raw bytes of an `SHT_GNU_HASH`
+    /// section.
+    ///
+    /// `nsyms` is the number of entries in the associated symbol table,
+    /// needed to size the hash-value chain.
+    pub fn new(data: &'a [u8], nsyms: usize) -> Option<Self> {
+        if data.len() < 16 {
+            return None;
+        }
+        let words = data.as_ptr() as *const u32;
+        let nbuckets = unsafe { *words } as usize;
+        let symoffset = unsafe { *words.offset(1) };
+        let bloom_size = unsafe { *words.offset(2) } as usize;
+        let bloom_shift = unsafe { *words.offset(3) };
+
+        let bloom_bytes = bloom_size * 8;
+        let header_bytes = 16;
+        if data.len() < header_bytes + bloom_bytes + nbuckets * 4 {
+            return None;
+        }
+        let bloom = unsafe {
+            slice::from_raw_parts(data[header_bytes..].as_ptr() as *const u64, bloom_size)
+        };
+        let bucket = unsafe {
+            slice::from_raw_parts(data[header_bytes + bloom_bytes..].as_ptr() as *const u32, nbuckets)
+        };
+        let chain_count = if nsyms > symoffset as usize { nsyms - symoffset as usize } else { 0 };
+        let chain_offset = header_bytes + bloom_bytes + nbuckets * 4;
+        if data.len() < chain_offset + chain_count * 4 {
+            return None;
+        }
+        let chain = unsafe {
+            slice::from_raw_parts(data[chain_offset..].as_ptr() as *const u32, chain_count)
+        };
+
+        Some(GnuHashTable {
+            symoffset: symoffset
+          , bloom_shift: bloom_shift
+          , bloom: bloom
+          , bucket: bucket
+          , chain: chain
+        })
+    }
+
+    /// Looks up `name` in `symtab`, whose entries' names are resolved
+    /// through `strtab`.
+    pub fn lookup(&self, name: &str, symtab: &'a [Symbol], strtab: &StrTab<'a>) -> Option<&'a Symbol> {
+        if self.bucket.is_empty() || self.bloom.is_empty() {
+            return None;
+        }
+        let hash = gnu_hash(name.as_bytes());
+
+        let word_bits = 64u32;
+        let word = self.bloom[(hash / word_bits) as usize % self.bloom.len()];
+        let mask = (1u64 << (hash % word_bits)) | (1u64 << ((hash >> self.bloom_shift) % word_bits));
+        if word & mask != mask {
+            return None;
+        }
+
+        let mut index = self.bucket[hash as usize % self.bucket.len()] as usize;
+        if index < self.symoffset as usize {
+            return None;
+        }
+
+        loop {
+            let chain_hash = match self.chain.get(index - self.symoffset as usize) {
+                Some(&h) => h
+              , None => return None
+            };
+            if chain_hash | 1 == hash | 1 {
+                if let Some(sym) = symtab.get(index) {
+                    if sym.name(strtab) == name {
+                        return Some(sym);
+                    }
+                }
+            }
+            if chain_hash & 1 != 0 {
+                return None;
+            }
+            index += 1;
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//
+//  SOS: the Stupid Operating System
+//  by Eliza Weisman (eliza@elizas.website)
+//
+//  Copyright (c) 2015-2017 Eliza Weisman
+//  Released under the terms of the MIT license. See `LICENSE` in the root
+//  directory of this repository for more information.
+//! Parsing of the Executable and Linkable Format (ELF).
+//!
+//! Refer to the [ELF standard](http://www.sco.com/developers/gabi/latest/ch4.sheader.html)
+//! for more information.
+
+pub mod compression;
+pub mod group;
+pub mod hash;
+pub mod note;
+pub mod relocation;
+pub mod section;
+pub mod strtab;
+pub mod symbol;
+
+pub use self::section::{Header, SectionTable, Type, Contents};
+pub use self::strtab::StrTab;
+pub use self::symbol::Symbol;
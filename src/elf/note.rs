@@ -0,0 +1,105 @@
+//
+//  SOS: the Stupid Operating System
+//  by Eliza Weisman (eliza@elizas.website)
+//
+//  Copyright (c) 2015-2017 Eliza Weisman
+//  Released under the terms of the MIT license. See `LICENSE` in the root
+//  directory of this repository for more information.
+//! ELF notes (`SHT_NOTE`).
+//!
+//! A note section holds a stream of tagged, named records -- most notably
+//! the GNU build-id, which the kernel can log to correlate a crash with
+//! the exact binary that produced it.
+
+use core::mem;
+use core::str;
+
+/// The `NT_GNU_BUILD_ID` note type, under the `"GNU"` owner name.
+pub const NT_GNU_BUILD_ID: u32 = 3;
+
+/// A single record from a `SHT_NOTE` section.
+#[derive(Copy, Clone)]
+pub struct Note<'a> {
+    name: &'a [u8]
+  , ty: u32
+  , desc: &'a [u8]
+}
+
+impl<'a> Note<'a> {
+    /// The note's owner name (e.g. `"GNU"`), or `""` if it is not valid
+    /// UTF-8.
+    pub fn name(&self) -> &'a str {
+        str::from_utf8(self.name).unwrap_or("")
+    }
+
+    /// The note's type. Interpretation is owner-specific; see
+    /// `NT_GNU_BUILD_ID`.
+    pub fn ty(&self) -> u32 {
+        self.ty
+    }
+
+    /// The note's descriptor bytes.
+    pub fn desc(&self) -> &'a [u8] {
+        self.desc
+    }
+}
+
+/// Rounds `n` up to the next multiple of 4, as note fields are padded.
+#[inline]
+fn align4(n: usize) -> usize {
+    (n + 3) & !3
+}
+
+/// An iterator over the notes in a `SHT_NOTE` section's data.
+///
+/// Constructed by `Header::notes`.
+pub struct Notes<'a> {
+    data: &'a [u8]
+}
+
+impl<'a> Notes<'a> {
+    /// Wraps the raw bytes of a note section.
+    pub fn new(data: &'a [u8]) -> Self {
+        Notes { data: data }
+    }
+}
+
+impl<'a> Iterator for Notes<'a> {
+    type Item = Note<'a>;
+
+    fn next(&mut self) -> Option<Note<'a>> {
+        let header_len = 3 * mem::size_of::<u32>();
+        if self.data.len() < header_len {
+            self.data = &[];
+            return None;
+        }
+
+        let words = self.data.as_ptr() as *const u32;
+        let namesz = unsafe { *words } as usize;
+        let descsz = unsafe { *words.offset(1) } as usize;
+        let ty = unsafe { *words.offset(2) };
+
+        let name_start = header_len;
+        let name_end = name_start + namesz;
+        let desc_start = name_start + align4(namesz);
+        let desc_end = desc_start + descsz;
+        let record_end = desc_start + align4(descsz);
+
+        if self.data.len() < record_end {
+            self.data = &[];
+            return None;
+        }
+
+        // Owner names are NUL-terminated; trim the terminator before
+        // exposing the name.
+        let mut name = &self.data[name_start..name_end];
+        if let Some((&0, rest)) = name.split_last() {
+            name = rest;
+        }
+        let desc = &self.data[desc_start..desc_end];
+
+        let note = Note { name: name, ty: ty, desc: desc };
+        self.data = &self.data[record_end..];
+        Some(note)
+    }
+}
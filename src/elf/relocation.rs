@@ -0,0 +1,170 @@
+//
+//  SOS: the Stupid Operating System
+//  by Eliza Weisman (eliza@elizas.website)
+//
+//  Copyright (c) 2015-2017 Eliza Weisman
+//  Released under the terms of the MIT license. See `LICENSE` in the root
+//  directory of this repository for more information.
+//! Relocations (`SHT_RELA` / `SHT_REL`).
+//!
+//! Relocatable object files (and the kernel modules built from them) defer
+//! filling in certain addresses until load time. Each `SHT_RELA` or
+//! `SHT_REL` section describes a set of such fixups to apply to another
+//! section once its final load address is known.
+
+use memory::PAddr;
+
+use elf::section::{self, Header as SectionHeader};
+use elf::symbol::Symbol;
+
+use core::slice;
+
+/// An `SHT_RELA` entry: a relocation with an explicit addend.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct Rela {
+    /// The location to be relocated, relative to the start of the section
+    /// this relocation applies to.
+    pub offset: PAddr
+  , /// The symbol table index and relocation type, packed together.
+    pub info: PAddr
+  , /// A constant added to the relocation's value.
+    pub addend: i64
+}
+
+/// An `SHT_REL` entry: a relocation without an explicit addend (the
+/// addend, if any, is read from the bytes being relocated).
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct Rel {
+    /// The location to be relocated, relative to the start of the section
+    /// this relocation applies to.
+    pub offset: PAddr
+  , /// The symbol table index and relocation type, packed together.
+    pub info: PAddr
+}
+
+impl Rela {
+    /// The index into the symbol table of the symbol this relocation
+    /// refers to.
+    #[inline] pub fn sym(&self) -> usize { (self.info.as_usize() >> 32) as usize }
+
+    /// The relocation type (an `R_X86_64_*` constant on x86_64).
+    #[inline] pub fn kind(&self) -> u32 { (self.info.as_usize() & 0xffffffff) as u32 }
+}
+
+impl Rel {
+    /// The index into the symbol table of the symbol this relocation
+    /// refers to.
+    #[inline] pub fn sym(&self) -> usize { (self.info.as_usize() >> 32) as usize }
+
+    /// The relocation type (an `R_X86_64_*` constant on x86_64).
+    #[inline] pub fn kind(&self) -> u32 { (self.info.as_usize() & 0xffffffff) as u32 }
+}
+
+// x86_64 relocation types we know how to apply.
+const R_X86_64_64: u32         = 1;
+const R_X86_64_PC32: u32       = 2;
+const R_X86_64_GLOB_DAT: u32   = 6;
+const R_X86_64_JUMP_SLOT: u32  = 7;
+const R_X86_64_RELATIVE: u32   = 8;
+
+/// Errors that can occur while applying relocations.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// `sym()` named a symbol-table index past the end of `symtab`.
+    BadSymbolIndex(usize)
+  , /// The relocation's target location falls outside `image`.
+    BadOffset
+  , /// The relocation type is not one we know how to apply.
+    UnknownType(u32)
+}
+
+/// Applies every relocation in `section` (an `SHT_RELA` or `SHT_REL`
+/// section) to `image`, a loaded copy of the section it refers to.
+///
+/// `file` is the raw ELF file bytes (used to read the relocation entries
+/// themselves), `symtab` is the symbol table the relocations' symbol
+/// indices are relative to, and `base` is the address `image` was loaded
+/// at.
+pub fn relocate(section: &SectionHeader, file: &[u8], symtab: &[Symbol], base: PAddr, image: &mut [u8]) -> Result<(), Error> {
+    match section.section_type() {
+        section::Type::Rela => {
+            for rela in rela_entries(section, file) {
+                apply(rela.offset.as_usize(), rela.sym(), rela.kind(), rela.addend, symtab, base, image)?;
+            }
+            Ok(())
+        }
+      , section::Type::Rel => {
+            for rel in rel_entries(section, file) {
+                let offset = rel.offset.as_usize();
+                let addend = read_addend(image, offset, rel.kind())?;
+                apply(offset, rel.sym(), rel.kind(), addend, symtab, base, image)?;
+            }
+            Ok(())
+        }
+      , _ => Ok(())
+    }
+}
+
+fn rela_entries<'a>(section: &SectionHeader, file: &'a [u8]) -> slice::Iter<'a, Rela> {
+    let bytes = section.raw_data(file);
+    let entry_length = section.entry_length().as_usize();
+    let count = if entry_length == 0 { 0 } else { bytes.len() / entry_length };
+    let ptr = bytes.as_ptr() as *const Rela;
+    unsafe { slice::from_raw_parts(ptr, count) }.iter()
+}
+
+fn rel_entries<'a>(section: &SectionHeader, file: &'a [u8]) -> slice::Iter<'a, Rel> {
+    let bytes = section.raw_data(file);
+    let entry_length = section.entry_length().as_usize();
+    let count = if entry_length == 0 { 0 } else { bytes.len() / entry_length };
+    let ptr = bytes.as_ptr() as *const Rel;
+    unsafe { slice::from_raw_parts(ptr, count) }.iter()
+}
+
+fn read_addend(image: &[u8], offset: usize, kind: u32) -> Result<i64, Error> {
+    let width = target_width(kind);
+    let bytes = image.get(offset..offset + width).ok_or(Error::BadOffset)?;
+    let mut value: u64 = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        value |= (b as u64) << (8 * i);
+    }
+    Ok(value as i64)
+}
+
+fn target_width(kind: u32) -> usize {
+    match kind {
+        R_X86_64_PC32 => 4
+      , _ => 8
+    }
+}
+
+fn apply(offset: usize, sym: usize, kind: u32, addend: i64, symtab: &[Symbol], base: PAddr, image: &mut [u8]) -> Result<(), Error> {
+    let p = base.as_usize() + offset;
+
+    let value: u64 = match kind {
+        R_X86_64_RELATIVE => (base.as_usize() as i64 + addend) as u64
+      , R_X86_64_64 => {
+            let s = symbol_addr(symtab, sym)?;
+            (s as i64 + addend) as u64
+        }
+      , R_X86_64_PC32 => {
+            let s = symbol_addr(symtab, sym)?;
+            (s as i64 + addend - p as i64) as u64
+        }
+      , R_X86_64_GLOB_DAT | R_X86_64_JUMP_SLOT => symbol_addr(symtab, sym)? as u64
+      , other => return Err(Error::UnknownType(other))
+    };
+
+    let width = target_width(kind);
+    let bytes = image.get_mut(offset..offset + width).ok_or(Error::BadOffset)?;
+    for i in 0..width {
+        bytes[i] = (value >> (8 * i)) as u8;
+    }
+    Ok(())
+}
+
+fn symbol_addr(symtab: &[Symbol], sym: usize) -> Result<usize, Error> {
+    symtab.get(sym).map(|s| s.value.as_usize()).ok_or(Error::BadSymbolIndex(sym))
+}
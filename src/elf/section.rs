@@ -1,7 +1,13 @@
 use memory::PAddr;
 
+use elf::strtab::StrTab;
+use elf::symbol::Symbol;
+use elf::compression::{self, CompressionHeader, ELFCOMPRESS_ZLIB};
+use elf::note::{Notes, NT_GNU_BUILD_ID};
+
 use core::mem;
 use core::fmt;
+use core::slice;
 
 // Distinguished section indices.
 pub const SHN_UNDEF: u16        = 0;
@@ -72,7 +78,7 @@ impl fmt::LowerHex for Flags {
 }
 
 bitflags! {
-    flags GroupFlags: u32 {
+    pub flags GroupFlags: u32 {
         const GRP_COMDAT	=        0x1
       , const GRP_MASKOS	= 0x0ff00000
       , const GRP_MASKPROC	= 0xf0000000
@@ -81,6 +87,37 @@ bitflags! {
 
 impl Header {
 
+    /// Returns this section's type.
+    #[inline] pub fn section_type(&self) -> Type {
+        self.ty.as_type()
+    }
+
+    /// Returns the index of the section this section's `link` field
+    /// refers to.
+    ///
+    /// The meaning of `link` (and of `info`, below) depends on this
+    /// section's type; for a symbol table it is the associated string
+    /// table, for a group section it is the associated symbol table.
+    #[inline] pub fn link(&self) -> u32 {
+        self.link
+    }
+
+    /// Returns this section's `info` field.
+    ///
+    /// For a group section, this is the symbol table index of the
+    /// signature symbol identifying the group.
+    #[inline] pub fn info(&self) -> u32 {
+        self.info
+    }
+
+    /// Returns the size, in bytes, of one entry in this section, for
+    /// sections that hold a table of fixed-size entries (symbol tables,
+    /// relocation sections, and so on). 0 if the section holds no such
+    /// table.
+    #[inline] pub fn entry_length(&self) -> PAddr {
+        self.entry_length
+    }
+
     /// Returns true if this section is writable.
     #[inline] pub fn is_writable(&self) -> bool {
         self.flags.contains(SHF_WRITE)
@@ -105,6 +142,162 @@ impl Header {
     #[inline] pub fn is_uniform(&self) -> bool {
         self.flags.contains(SHF_MERGE) && !self.flags.contains(SHF_STRINGS)
     }
+
+    /// Returns the raw bytes of this section, sliced out of the full file
+    /// `data`.
+    ///
+    /// This is the basis for every section-contents accessor (string
+    /// tables, symbol tables, and so on), since a `Header` only knows its
+    /// `offset` and `length` within the file.
+    pub fn raw_data<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+        let start = self.offset.as_usize();
+        let end = start + self.length.as_usize();
+        &data[start..end]
+    }
+
+    /// Resolves this section's `name_offset` into its name, using the
+    /// given section-header string table.
+    ///
+    /// Returns `""` if the offset cannot be resolved.
+    pub fn name<'a>(&self, strtab: &StrTab<'a>) -> &'a str {
+        strtab.get(self.name_offset).unwrap_or("")
+    }
+
+    /// Interprets this section's data as a symbol table and returns an
+    /// iterator over its entries.
+    ///
+    /// This is only meaningful for sections of type `Type::SymbolTable` or
+    /// `Type::DynSymTable`; resolve each entry's name through the string
+    /// table named by this section's `link` field (see
+    /// `SectionTable::linked_strtab`).
+    pub fn symbols<'a>(&self, data: &'a [u8]) -> slice::Iter<'a, Symbol> {
+        let bytes = self.raw_data(data);
+        let entry_length = self.entry_length.as_usize();
+        let count = if entry_length == 0 { 0 } else { bytes.len() / entry_length };
+        let ptr = bytes.as_ptr() as *const Symbol;
+        unsafe { slice::from_raw_parts(ptr, count) }.iter()
+    }
+
+    /// Reads the `CompressionHeader` prepended to this section's data, if
+    /// `SHF_COMPRESSED` is set.
+    pub fn compression_header(&self, data: &[u8]) -> Option<CompressionHeader> {
+        if !self.flags.contains(SHF_COMPRESSED) {
+            return None;
+        }
+        let bytes = self.raw_data(data);
+        if bytes.len() < mem::size_of::<CompressionHeader>() {
+            return None;
+        }
+        let ptr = bytes.as_ptr() as *const CompressionHeader;
+        Some(unsafe { *ptr })
+    }
+
+    /// Returns the size this section's data will occupy once decompressed.
+    ///
+    /// Returns `None` if the section is not `SHF_COMPRESSED`.
+    pub fn uncompressed_len(&self, data: &[u8]) -> Option<u64> {
+        self.compression_header(data).map(|header| header.ch_size)
+    }
+
+    /// Decompresses this section's data into `out`.
+    ///
+    /// `out` must be at least `self.uncompressed_len(data)` bytes long.
+    pub fn decompress_into(&self, data: &[u8], out: &mut [u8]) -> Result<(), compression::Error> {
+        let header = self.compression_header(data).ok_or(compression::Error::Truncated)?;
+        if (out.len() as u64) < header.ch_size {
+            return Err(compression::Error::OutputTooSmall);
+        }
+        let payload = &self.raw_data(data)[mem::size_of::<CompressionHeader>()..];
+        let out = &mut out[..header.ch_size as usize];
+        match header.ch_type {
+            ELFCOMPRESS_ZLIB => compression::inflate_zlib(payload, out)
+          , other => Err(compression::Error::UnknownType(other))
+        }
+    }
+
+    /// Interprets this section's data as a `SHT_NOTE` stream and returns an
+    /// iterator over its records.
+    pub fn notes<'a>(&self, data: &'a [u8]) -> Notes<'a> {
+        Notes::new(self.raw_data(data))
+    }
+
+    /// Finds the GNU build-id note (owner `"GNU"`, type
+    /// `NT_GNU_BUILD_ID`) in this section's notes, if present.
+    ///
+    /// Only meaningful for sections of type `Type::Notes`.
+    pub fn build_id<'a>(&self, data: &'a [u8]) -> Option<&'a [u8]> {
+        self.notes(data)
+            .find(|note| note.ty() == NT_GNU_BUILD_ID && note.name() == "GNU")
+            .map(|note| note.desc())
+    }
+
+    /// Interprets this section's data according to its type.
+    ///
+    /// Currently only `Type::Group` is given a structured interpretation;
+    /// `Type::Null` sections, and `Type::Group` sections too short to hold
+    /// their flag word, yield `Contents::Empty`, and every other type is
+    /// returned as an opaque byte slice.
+    pub fn contents<'a>(&self, data: &'a [u8]) -> Contents<'a> {
+        let bytes = self.raw_data(data);
+        match self.section_type() {
+            Type::Null => Contents::Empty
+          , Type::Group => {
+                let word = mem::size_of::<u32>();
+                if bytes.len() < word {
+                    return Contents::Empty;
+                }
+                let flags = unsafe { &*(bytes.as_ptr() as *const u32) };
+                let rest = &bytes[word..];
+                let count = rest.len() / word;
+                let indicies = unsafe { slice::from_raw_parts(rest.as_ptr() as *const u32, count) };
+                Contents::Group { flags: flags, indicies: indicies }
+            }
+          , _ => Contents::Undefined(bytes)
+        }
+    }
+}
+
+/// A section-header table paired with the section-header string table
+/// (`.shstrtab`) that gives its entries names.
+///
+/// This is the entry point for resolving section names; see `Header::name`.
+pub struct SectionTable<'a> {
+    data: &'a [u8]
+  , sections: &'a [Header]
+  , shstrtab: StrTab<'a>
+}
+
+impl<'a> SectionTable<'a> {
+    /// Constructs a `SectionTable` from the section-header array and the
+    /// index of the section-header string table (the ELF header's
+    /// `e_shstrndx`).
+    pub fn new(data: &'a [u8], sections: &'a [Header], shstrndx: u16) -> Self {
+        let shstrtab = StrTab::new(sections[shstrndx as usize].raw_data(data));
+        SectionTable { data: data, sections: sections, shstrtab: shstrtab }
+    }
+
+    /// Returns the section headers in this table.
+    pub fn sections(&self) -> &'a [Header] {
+        self.sections
+    }
+
+    /// Returns the section-header string table backing this table.
+    pub fn strtab(&self) -> &StrTab<'a> {
+        &self.shstrtab
+    }
+
+    /// Returns the raw bytes backing `section`.
+    pub fn data_of(&self, section: &Header) -> &'a [u8] {
+        section.raw_data(self.data)
+    }
+
+    /// Returns the string table named by `section`'s `link` field.
+    ///
+    /// This is how a symbol table (`link` pointing at `.strtab` or
+    /// `.dynstr`) resolves its entries' names.
+    pub fn linked_strtab(&self, section: &Header) -> StrTab<'a> {
+        StrTab::new(self.sections[section.link as usize].raw_data(self.data))
+    }
 }
 
 pub enum Contents<'a> {
@@ -134,6 +327,8 @@ impl TypeRepr {
           , 14 => Type::InitArray
           , 15 => Type::FiniArray
           , 16 => Type::PreInitArray
+          , 17 => Type::Group
+          , 18 => Type::SymbolTableShIndex
           , x @ SHT_LOOS ... SHT_HIOS => Type::OSSpecific(x)
           , x @ SHT_LOPROC ... SHT_HIPROC => Type::ProcessorSpecific(x)
           , x @ SHT_LOUSER ... SHT_HIUSER => Type::User(x)
@@ -0,0 +1,43 @@
+//
+//  SOS: the Stupid Operating System
+//  by Eliza Weisman (eliza@elizas.website)
+//
+//  Copyright (c) 2015-2017 Eliza Weisman
+//  Released under the terms of the MIT license. See `LICENSE` in the root
+//  directory of this repository for more information.
+//! ELF string tables (`SHT_STRTAB`).
+//!
+//! A string table section is simply a run of NUL-terminated strings,
+//! addressed by byte offset from other structures (section headers, symbol
+//! table entries, dynamic entries, &c).
+
+use core::str;
+
+/// A view of a `SHT_STRTAB` section's raw bytes.
+///
+/// Refer to [`Header::name`](../section/struct.Header.html#method.name) and
+/// [`Symbol::name`](../symbol/struct.Symbol.html#method.name) for the
+/// primary consumers of this type.
+#[derive(Copy, Clone)]
+pub struct StrTab<'a>(&'a [u8]);
+
+impl<'a> StrTab<'a> {
+    /// Wraps the raw bytes of a string-table section.
+    pub fn new(data: &'a [u8]) -> Self {
+        StrTab(data)
+    }
+
+    /// Looks up the NUL-terminated string beginning at `offset`.
+    ///
+    /// Returns `None` if `offset` is out of bounds or the bytes from
+    /// `offset` to the next NUL are not valid UTF-8.
+    pub fn get(&self, offset: u32) -> Option<&'a str> {
+        let offset = offset as usize;
+        if offset >= self.0.len() {
+            return None;
+        }
+        let rest = &self.0[offset..];
+        let end = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+        str::from_utf8(&rest[..end]).ok()
+    }
+}
@@ -0,0 +1,115 @@
+//
+//  SOS: the Stupid Operating System
+//  by Eliza Weisman (eliza@elizas.website)
+//
+//  Copyright (c) 2015-2017 Eliza Weisman
+//  Released under the terms of the MIT license. See `LICENSE` in the root
+//  directory of this repository for more information.
+//! ELF symbol tables (`SHT_SYMTAB` / `SHT_DYNSYM`).
+
+use memory::PAddr;
+
+use elf::strtab::StrTab;
+
+use core::fmt;
+
+/// An entry in a `SHT_SYMTAB` or `SHT_DYNSYM` section.
+///
+/// Refer to the [ELF standard](http://www.sco.com/developers/gabi/latest/ch4.symtab.html)
+/// for more information.
+#[repr(C)]
+pub struct Symbol {
+    /// An index into the linked string table, giving the symbol's name.
+    name: u32
+  , /// The symbol's binding and type, packed into a single byte.
+    info: u8
+  , /// Currently unused; holds 0.
+    other: u8
+  , /// The section index this symbol is defined in relation to.
+    pub shndx: u16
+  , /// The value of the symbol. Depending on the symbol, this may be an
+    /// absolute value, an address, and so on.
+    pub value: PAddr
+  , /// The size of the object the symbol describes, or 0 if the symbol has
+    /// no size or an unknown size.
+    pub size: PAddr
+}
+
+/// A symbol's binding, decoded from the high nibble of `Symbol::info`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Binding {
+    /// `STB_LOCAL`: not visible outside the object file containing its
+    /// definition.
+    Local
+  , /// `STB_GLOBAL`: visible to all object files being combined.
+    Global
+  , /// `STB_WEAK`: like `Global`, but with lower precedence.
+    Weak
+  , /// A processor- or OS-specific binding.
+    Other(u8)
+}
+
+/// A symbol's type, decoded from the low nibble of `Symbol::info`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SymType {
+    /// `STT_NOTYPE`: the symbol's type is not specified.
+    NoType
+  , /// `STT_OBJECT`: the symbol is associated with a data object.
+    Object
+  , /// `STT_FUNC`: the symbol is associated with a function or other
+    /// executable code.
+    Func
+  , /// `STT_SECTION`: the symbol is associated with a section.
+    Section
+  , /// `STT_FILE`: the symbol's name gives the name of the source file
+    /// associated with the object file.
+    File
+  , /// A processor- or OS-specific type.
+    Other(u8)
+}
+
+impl Symbol {
+    /// Returns this symbol's binding.
+    #[inline]
+    pub fn binding(&self) -> Binding {
+        match self.info >> 4 {
+            0 => Binding::Local
+          , 1 => Binding::Global
+          , 2 => Binding::Weak
+          , n => Binding::Other(n)
+        }
+    }
+
+    /// Returns this symbol's type.
+    #[inline]
+    pub fn sym_type(&self) -> SymType {
+        match self.info & 0xf {
+            0 => SymType::NoType
+          , 1 => SymType::Object
+          , 2 => SymType::Func
+          , 3 => SymType::Section
+          , 4 => SymType::File
+          , n => SymType::Other(n)
+        }
+    }
+
+    /// Resolves this symbol's name through `strtab`.
+    ///
+    /// Returns `""` if the name cannot be resolved.
+    pub fn name<'a>(&self, strtab: &StrTab<'a>) -> &'a str {
+        strtab.get(self.name).unwrap_or("")
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Symbol")
+         .field("name", &self.name)
+         .field("binding", &self.binding())
+         .field("sym_type", &self.sym_type())
+         .field("shndx", &self.shndx)
+         .field("value", &self.value)
+         .field("size", &self.size)
+         .finish()
+    }
+}